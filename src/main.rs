@@ -2,102 +2,333 @@ use std::{
     error::Error,
     future::pending,
     fs::OpenOptions,
-    io::{Read, Write}
+    io::{Read, Write},
+    sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering}},
+    time::{Duration, Instant}
 };
 
-use zbus::{ConnectionBuilder, dbus_interface};
+use futures_util::StreamExt;
+use inotify::{Inotify, WatchMask};
+use zbus::{Connection, ConnectionBuilder, SignalContext, dbus_interface};
+
+/// Errors surfaced by the kernel-interface helpers.
+///
+/// Converted into `zbus::fdo::Error` variants on the way out so a
+/// missing device or a transient read/write failure is returned to
+/// the caller as a proper D-Bus error instead of panicking and
+/// taking the whole service down.
+#[derive(Debug)]
+enum BlctlError {
+    /// An I/O failure opening, reading or writing a sysfs file.
+    Io(std::io::Error),
+
+    /// The sysfs data could not be parsed as a brightness value.
+    Parse(std::num::ParseIntError),
+
+    /// The logind fallback (see [logind_set_brightness]) failed.
+    Bus(zbus::Error)
+}
+
+impl std::fmt::Display for BlctlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlctlError::Io(err) => write!(f, "kernel interface I/O error: {}", err),
+            BlctlError::Parse(err) => write!(f, "failed to parse kernel interface data: {}", err),
+            BlctlError::Bus(err) => write!(f, "logind fallback failed: {}", err)
+        }
+    }
+}
+
+impl Error for BlctlError {}
+
+impl From<std::io::Error> for BlctlError {
+    fn from(err: std::io::Error) -> Self {
+        BlctlError::Io(err)
+    }
+}
+
+impl From<std::num::ParseIntError> for BlctlError {
+    fn from(err: std::num::ParseIntError) -> Self {
+        BlctlError::Parse(err)
+    }
+}
+
+impl From<zbus::Error> for BlctlError {
+    fn from(err: zbus::Error) -> Self {
+        BlctlError::Bus(err)
+    }
+}
+
+impl From<BlctlError> for zbus::fdo::Error {
+    fn from(err: BlctlError) -> Self {
+        match err {
+            BlctlError::Io(err) => zbus::fdo::Error::IOError(err.to_string()),
+            BlctlError::Parse(err) => zbus::fdo::Error::Failed(format!(
+                "failed to parse kernel interface data: {}",
+                err
+            )),
+            BlctlError::Bus(err) => zbus::fdo::Error::Failed(format!(
+                "logind fallback failed: {}",
+                err
+            ))
+        }
+    }
+}
 
 struct BacklightController {
-    kernel_brightness_fp: String,
-    kernel_max_brightness_fp: String
+    /// Sysfs paths for the active device, shared with the background
+    /// tasks so a [select_device] switch follows through to the
+    /// inotify watch (see [watch_brightness]) and the auto-brightness
+    /// poller (see [watch_ambient_light]).
+    paths: Arc<DevicePaths>,
+
+    /// Minimum brightness level, clamped alongside `max` so `set`
+    /// and `decrease` can't drive the panel to an unreadable/off
+    /// level (see [set_min]).
+    min: u32,
+
+    /// Generation counter used to cancel an in-flight [set_smooth]
+    /// fade when a newer transition arrives. Every `set`/`set_smooth`
+    /// bumps it; a running fade bails out as soon as it no longer
+    /// owns the latest generation.
+    fade_generation: Arc<AtomicU64>,
+
+    /// Shared state for the ambient-light auto-brightness subsystem,
+    /// mutated from the D-Bus methods and read by the background
+    /// polling task (see [watch_ambient_light]).
+    auto: Arc<AutoState>
+}
+
+/// The sysfs brightness paths for the currently selected device.
+///
+/// Shared behind an `Arc` between the D-Bus interface and the
+/// background tasks so that changing the active device (see
+/// [BacklightController::select_device]) is reflected everywhere
+/// rather than leaving the watchers pinned to the startup device.
+struct DevicePaths {
+    brightness: Mutex<String>,
+    max_brightness: Mutex<String>
+}
+
+impl DevicePaths {
+    fn new(brightness: String, max_brightness: String) -> Self {
+        DevicePaths {
+            brightness: Mutex::new(brightness),
+            max_brightness: Mutex::new(max_brightness)
+        }
+    }
+
+    fn brightness(&self) -> String {
+        self.brightness.lock().unwrap().clone()
+    }
+
+    fn max_brightness(&self) -> String {
+        self.max_brightness.lock().unwrap().clone()
+    }
+}
+
+/// How long manual brightness changes suppress auto-adjustments, so
+/// a user override sticks instead of being immediately overwritten.
+const AUTO_GRACE: Duration = Duration::from_secs(5);
+
+/// State driving the ambient-light auto-brightness subsystem, shared
+/// between the D-Bus interface and the background polling task.
+struct AutoState {
+    /// Whether auto-brightness is currently active.
+    enabled: AtomicBool,
+
+    /// Minimum brightness as a percentage of `max`, so the display
+    /// never fully blacks out under a dark sensor reading.
+    min_percent: AtomicU32,
+
+    /// User-supplied `(lux, brightness%)` control points, sorted by
+    /// lux, interpolated with a monotone spline (see
+    /// [interpolate_curve]).
+    curve: Mutex<Vec<(f64, u32)>>,
+
+    /// Timestamp of the last manual brightness change, used to honour
+    /// the [AUTO_GRACE] suppression window.
+    last_manual: Mutex<Option<Instant>>
+}
+
+impl AutoState {
+    fn new() -> Self {
+        AutoState {
+            enabled: AtomicBool::new(false),
+            min_percent: AtomicU32::new(1),
+            curve: Mutex::new(Vec::new()),
+            last_manual: Mutex::new(None)
+        }
+    }
+
+    /// Records that a manual change just happened, opening an
+    /// [AUTO_GRACE] window during which auto-adjustments are skipped.
+    fn mark_manual(&self) {
+        *self.last_manual.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns `true` while inside the post-manual grace window.
+    fn in_grace(&self) -> bool {
+        match *self.last_manual.lock().unwrap() {
+            Some(when) => when.elapsed() < AUTO_GRACE,
+            None => false
+        }
+    }
 }
 
 #[dbus_interface(name = "me.xela.blctl1")]
 impl BacklightController {
+    /// Lists the backlight devices exposed by the kernel.
+    ///
+    /// Scans `/sys/class/backlight` and returns the name of every
+    /// entry (e.g. `amdgpu_bl0`, `intel_backlight`). The returned
+    /// names can be passed as the `device` argument of the other
+    /// methods to drive a specific backlight.
+    async fn list_devices(&mut self) -> Vec<String> {
+        println!("Recieved 'list_devices()' message");
+
+        match std::fs::read_dir("/sys/class/backlight") {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(_) => Vec::new()
+        }
+    }
+
+    /// Selects the backlight device subsequent calls operate on.
+    ///
+    /// Rebuilds the shared [DevicePaths] from the chosen
+    /// `/sys/class/backlight` entry, so the background tasks follow
+    /// the switch too.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a `/sys/class/backlight` entry as
+    /// returned by [list_devices].
+    async fn select_device(&mut self, name: String) {
+        println!("Recieved 'select_device(name: {})' message", name);
+
+        *self.paths.brightness.lock().unwrap() =
+            format!("/sys/class/backlight/{}/brightness", name);
+        *self.paths.max_brightness.lock().unwrap() =
+            format!("/sys/class/backlight/{}/max_brightness", name);
+    }
+
+    /// Resolves the `(brightness, max_brightness)` sysfs paths for a
+    /// single call without touching the shared selection.
+    ///
+    /// An empty `device` yields the currently selected device's
+    /// paths; a named `device` yields that device's paths just for
+    /// this call, so read-only methods don't leak a selection into
+    /// the background tasks.
+    fn resolve_paths(&self, device: &str) -> (String, String) {
+        if device.is_empty() {
+            (self.paths.brightness(), self.paths.max_brightness())
+        } else {
+            (
+                format!("/sys/class/backlight/{}/brightness", device),
+                format!("/sys/class/backlight/{}/max_brightness", device),
+            )
+        }
+    }
+
     /// Increases the backlight brightness level.
     ///
     /// # Arguments
     ///
+    /// * `device` - The backlight device to operate on, as
+    /// returned by [list_devices]. When empty the currently
+    /// selected device is used (see [select_device]).
     /// * `amount` - The backlight brightness level to increase
     /// by as a percentage of the maximum supported backlight
     /// brightness level (see [max]).
-    async fn increase(&mut self, amount: u32) {
-        println!("Received 'increase(amount: {})' message", amount);
+    async fn increase(&mut self, device: String, amount: u32) -> zbus::fdo::Result<()> {
+        println!("Received 'increase(device: {}, amount: {})' message", device, amount);
 
-        // let current = self.get().await;
-        let current = ki_read(&self.kernel_brightness_fp)
+        if !device.is_empty() {
+            self.select_device(device).await;
+        }
+
+        // Cancel any in-flight fade so the step override wins.
+        self.fade_generation.fetch_add(1, Ordering::SeqCst);
+
+        // Suppress auto-brightness so the override sticks.
+        self.auto.mark_manual();
+
+        let brightness_fp = self.paths.brightness();
+        let max_brightness_fp = self.paths.max_brightness();
+
+        let current = ki_read(&brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) data to u32",
-                self.kernel_brightness_fp
-            ).as_str());
+            .map_err(BlctlError::from)?;
 
-        // let max = self.max().await;
-        let max = ki_read(&self.kernel_max_brightness_fp)
+        let max = ki_read(&max_brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) to u32",
-                &self.kernel_max_brightness_fp
-            ).as_str());
+            .map_err(BlctlError::from)?;
 
         let fraction = max as f32 / 100f32;
         let actual_amount = fraction * amount as f32;
 
-        let mut new_current = current + actual_amount as u32;
-        
-        if new_current > max {
-            new_current = max;
-        }
+        let new_current = clamp_brightness(current + actual_amount as u32, self.min, max);
 
         ki_write(
-            &self.kernel_brightness_fp,
+            &brightness_fp,
             new_current.to_string()
-        );
+        )?;
+
+        Ok(())
     }
 
     /// Decreases the backlight brightness level.
-    /// 
+    ///
     /// # Arguments
     ///
+    /// * `device` - The backlight device to operate on, as
+    /// returned by [list_devices]. When empty the currently
+    /// selected device is used (see [select_device]).
     /// * `amount` - The backlight brightness level to reduce
     /// by as a percentage of the maximum supported backlight
     /// brightness level (see [max]).
-    async fn decrease(&mut self, amount: u32) {
-        println!("Received 'decrease(amount: {})' message", amount);
+    async fn decrease(&mut self, device: String, amount: u32) -> zbus::fdo::Result<()> {
+        println!("Received 'decrease(device: {}, amount: {})' message", device, amount);
 
-        // let mut current = self.get().await;
-        let mut current = ki_read(&self.kernel_brightness_fp)
+        if !device.is_empty() {
+            self.select_device(device).await;
+        }
+
+        // Cancel any in-flight fade so the step override wins.
+        self.fade_generation.fetch_add(1, Ordering::SeqCst);
+
+        // Suppress auto-brightness so the override sticks.
+        self.auto.mark_manual();
+
+        let brightness_fp = self.paths.brightness();
+        let max_brightness_fp = self.paths.max_brightness();
+
+        let current = ki_read(&brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) data to u32",
-                self.kernel_brightness_fp
-            ).as_str());
-        
-        // let max = self.max().await;
-        let max = ki_read(&self.kernel_max_brightness_fp)
+            .map_err(BlctlError::from)?;
+
+        let max = ki_read(&max_brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) data to u32",
-                self.kernel_max_brightness_fp
-            ).as_str());
+            .map_err(BlctlError::from)?;
 
         let fraction = max as f32 / 100f32;
         let actual_amount = fraction * amount as f32;
 
-        // Prevent u32 underflow
-        if current < actual_amount as u32 {
-            current = actual_amount as u32;
-        }
+        let new_current = decrease_brightness(current, actual_amount as u32, self.min, max);
 
-        let new_current = current - actual_amount as u32;
         ki_write(
-            &self.kernel_brightness_fp,
+            &brightness_fp,
             new_current.to_string()
-        );
+        )?;
+
+        Ok(())
     }
 
     /// Sets the backlight brightness level to the specified
@@ -105,94 +336,653 @@ impl BacklightController {
     ///
     /// # Arguments
     ///
+    /// * `device` - The backlight device to operate on, as
+    /// returned by [list_devices]. When empty the currently
+    /// selected device is used (see [select_device]).
     /// * `value` - The brightness level to set the backlight
     /// to. Clamped between 0 and the maximum supported
     /// backlight brightness level (see [max]).
-    async fn set(&mut self, mut value: u32) {
-        println!("Recieved 'set(value: {})' message", value);
+    async fn set(&mut self, device: String, value: u32) -> zbus::fdo::Result<()> {
+        println!("Recieved 'set(device: {}, value: {})' message", device, value);
 
-        let max = self.max().await;
-        if value > max {
-            value = max;
+        if !device.is_empty() {
+            self.select_device(device).await;
         }
 
-        ki_write(&self.kernel_brightness_fp, value.to_string());
+        // Cancel any in-flight fade so an instant set wins.
+        self.fade_generation.fetch_add(1, Ordering::SeqCst);
+
+        // Suppress auto-brightness so the override sticks.
+        self.auto.mark_manual();
+
+        let max = self.max(String::new()).await?;
+        let value = clamp_brightness(value, self.min, max);
+
+        ki_write(&self.paths.brightness(), value.to_string())?;
+
+        Ok(())
+    }
+
+    /// Sets the backlight brightness level, ramping smoothly from
+    /// the current level to the target over `duration_ms`.
+    ///
+    /// The trajectory is sampled in ~16ms steps and interpolated
+    /// with an ease-in-out (smoothstep) curve so both the start and
+    /// end velocities are zero, giving a gentle fade rather than a
+    /// linear sweep. Each intermediate value is clamped to
+    /// `[min, max]`. A new `set`/`set_smooth` cancels a fade already
+    /// in flight so a burst of keypresses doesn't fight itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The backlight device to operate on, as
+    /// returned by [list_devices]. When empty the currently
+    /// selected device is used (see [select_device]).
+    /// * `value` - The target brightness level. Clamped between the
+    /// minimum brightness floor (see [min]) and the maximum supported
+    /// backlight brightness level (see [max]).
+    /// * `duration_ms` - The duration of the fade in milliseconds.
+    async fn set_smooth(&mut self, device: String, value: u32, duration_ms: u32) -> zbus::fdo::Result<()> {
+        println!(
+            "Recieved 'set_smooth(device: {}, value: {}, duration_ms: {})' message",
+            device, value, duration_ms
+        );
+
+        if !device.is_empty() {
+            self.select_device(device).await;
+        }
+
+        // Suppress auto-brightness so the override sticks.
+        self.auto.mark_manual();
+
+        let max = self.max(String::new()).await?;
+        // `min.min(max)` keeps the low bound from ever exceeding the
+        // high bound, so the per-step clamps below can't panic on a
+        // large `set_min`.
+        let min = self.min.min(max);
+        let target = clamp_brightness(value, self.min, max);
+        let current = self.get(String::new()).await?;
+
+        // Claim a new generation, cancelling any running fade.
+        let generation = self.fade_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let fade_generation = self.fade_generation.clone();
+        let kernel_brightness_fp = self.paths.brightness();
+
+        // ~16ms per write, at least one step.
+        let steps = (duration_ms / 16).max(1);
+        let step_sleep = Duration::from_millis((duration_ms / steps) as u64);
+
+        async_std::task::spawn(async move {
+            for step in 1..=steps {
+                // Bail out if a newer transition superseded this one.
+                if fade_generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+
+                let t = step as f32 / steps as f32;
+                // Smoothstep: zero velocity at t=0 and t=1.
+                let eased = t * t * (3.0 - 2.0 * t);
+
+                let delta = target as f32 - current as f32;
+                let next = (current as f32 + delta * eased)
+                    .round()
+                    .clamp(min as f32, max as f32) as u32;
+
+                if let Err(err) = ki_write(&kernel_brightness_fp, next.to_string()) {
+                    eprintln!("fade write failed: {:?}", err);
+                    return;
+                }
+
+                async_std::task::sleep(step_sleep).await;
+            }
+        });
+
+        Ok(())
     }
 
     /// Returns the current backlight brightness level.
-    async fn get(&mut self) -> u32 {
-        println!("Recieved 'get()' message");
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The backlight device to read, as returned by
+    /// [list_devices]. When empty the currently selected device is
+    /// used (see [select_device]). Reading a named device does not
+    /// change the selection.
+    async fn get(&mut self, device: String) -> zbus::fdo::Result<u32> {
+        println!("Recieved 'get(device: {})' message", device);
 
-        ki_read(&self.kernel_brightness_fp)
+        let (brightness_fp, _) = self.resolve_paths(&device);
+
+        let value = ki_read(&brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) data to u32",
-                &self.kernel_brightness_fp
-            ).as_str())
+            .map_err(BlctlError::from)?;
+
+        Ok(value)
     }
 
     /// Returns the maximum support backlight brightness level.
-    async fn max(&mut self) -> u32 {
-        println!("Recieved 'max()' mesage");
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The backlight device to read, as returned by
+    /// [list_devices]. When empty the currently selected device is
+    /// used (see [select_device]). Reading a named device does not
+    /// change the selection.
+    async fn max(&mut self, device: String) -> zbus::fdo::Result<u32> {
+        println!("Recieved 'max(device: {})' mesage", device);
+
+        let (_, max_brightness_fp) = self.resolve_paths(&device);
 
-        ki_read(&self.kernel_max_brightness_fp)
+        let value = ki_read(&max_brightness_fp)?
             .trim()
             .parse::<u32>()
-            .expect(format!(
-                "failed to parse kernel interface ({}) data to u32",
-                &self.kernel_max_brightness_fp
-            ).as_str())
+            .map_err(BlctlError::from)?;
+
+        Ok(value)
+    }
+
+    /// Returns the minimum brightness floor.
+    async fn min(&mut self) -> u32 {
+        println!("Recieved 'min()' message");
+
+        self.min
+    }
+
+    /// Sets the minimum brightness floor.
+    ///
+    /// `set` and `decrease` clamp at this floor so the panel can't be
+    /// driven to an unreadable/off level.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The lowest brightness level, in kernel units.
+    async fn set_min(&mut self, value: u32) {
+        println!("Recieved 'set_min(value: {})' message", value);
+
+        self.min = value;
+    }
+
+    /// Enables or disables ambient-light auto-brightness.
+    ///
+    /// When enabled, a background task polls the ambient light sensor
+    /// and maps the reading onto the backlight through the curve set
+    /// by [set_auto_curve].
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether auto-brightness should be active.
+    async fn set_auto(&mut self, enabled: bool) {
+        println!("Recieved 'set_auto(enabled: {})' message", enabled);
+
+        self.auto.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Sets the control points mapping ambient light to brightness.
+    ///
+    /// The points are sorted by lux and interpolated with a monotone
+    /// spline (see [interpolate_curve]) so the response is smooth
+    /// rather than stepwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - `(lux, brightness%)` control points. The
+    /// brightness component is a percentage of the maximum supported
+    /// level (see [max]).
+    async fn set_auto_curve(&mut self, mut points: Vec<(f64, u32)>) {
+        println!("Recieved 'set_auto_curve(points: {:?})' message", points);
+
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        // Drop duplicate lux control points, which would otherwise
+        // make [interpolate_curve] divide by a zero segment width and
+        // produce NaN.
+        points.dedup_by(|a, b| a.0 == b.0);
+        *self.auto.curve.lock().unwrap() = points;
+    }
+
+    /// Sets the minimum auto-brightness floor.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - The lowest brightness auto-brightness may set,
+    /// as a percentage of the maximum supported level, so the
+    /// display never fully blacks out.
+    async fn set_auto_min(&mut self, percent: u32) {
+        println!("Recieved 'set_auto_min(percent: {})' message", percent);
+
+        self.auto.min_percent.store(percent.min(100), Ordering::SeqCst);
     }
+
+    /// Emitted whenever the kernel brightness value changes.
+    ///
+    /// Fires for our own writes as well as writes made by another
+    /// process, so consumers (status bars, OSD popups) can react
+    /// event-driven instead of polling [get]. Changes made inside the
+    /// kernel, such as by a hardware brightness key, do not fire it
+    /// (see [watch_brightness]).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new brightness level.
+    /// * `max` - The maximum supported backlight brightness level
+    /// (see [max]).
+    #[dbus_interface(signal)]
+    async fn brightness_changed(
+        ctxt: &SignalContext<'_>,
+        value: u32,
+        max: u32
+    ) -> zbus::Result<()>;
 }
 
-fn ki_read(filepath: &String) -> String {
+fn ki_read(filepath: &String) -> Result<String, BlctlError> {
     let mut file = OpenOptions::new()
         .read(true)
         .create(false)
-        .open(&filepath)
-        .expect(format!(
-                "failed to open kernel interface ({}) for reading",
-                &filepath
-                ).as_str());
+        .open(&filepath)?;
 
     let mut data = String::new();
-    file.read_to_string(&mut data)
-        .expect(format!(
-                "failed to read data from kernel interface ({})",
-                &filepath
-                ).as_str());
+    file.read_to_string(&mut data)?;
 
     println!(
-        "Read data ({:?}) from kernel interface ({})", 
-        &data.as_bytes(), 
+        "Read data ({:?}) from kernel interface ({})",
+        &data.as_bytes(),
         &filepath
     );
 
-    data
+    Ok(data)
 }
 
-fn ki_write(filepath: &String, data: String) {
-    let mut file = OpenOptions::new()
+/// Sets the backlight brightness through logind.
+///
+/// Used as a fallback by [ki_write] when the sysfs file can't be
+/// opened for writing because the service lacks the privileges to do
+/// so directly. Resolves the current session (via `XDG_SESSION_ID`,
+/// falling back to the session owning this process) and calls
+/// `org.freedesktop.login1.Session.SetBrightness` with subsystem
+/// `"backlight"` and the device name parsed from `filepath`.
+fn logind_set_brightness(filepath: &String, value: u32) -> zbus::Result<()> {
+    use zbus::blocking::Connection;
+
+    let device = std::path::Path::new(filepath)
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let conn = Connection::system()?;
+
+    let session_path: zbus::zvariant::OwnedObjectPath =
+        if let Ok(session_id) = std::env::var("XDG_SESSION_ID") {
+            conn.call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1",
+                Some("org.freedesktop.login1.Manager"),
+                "GetSession",
+                &(session_id,)
+            )?.body()?
+        } else {
+            conn.call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1",
+                Some("org.freedesktop.login1.Manager"),
+                "GetSessionByPID",
+                &(std::process::id(),)
+            )?.body()?
+        };
+
+    conn.call_method(
+        Some("org.freedesktop.login1"),
+        &session_path,
+        Some("org.freedesktop.login1.Session"),
+        "SetBrightness",
+        &("backlight", device.as_str(), value)
+    )?;
+
+    Ok(())
+}
+
+fn ki_write(filepath: &String, data: String) -> Result<(), BlctlError> {
+    let file = OpenOptions::new()
         .write(true)
         .create(false)
-        .open(&filepath)
-        .expect(format!(
-            "failed to open kernel interface ({}) for writing",
-            &filepath
-        ).as_str());
-
-    file.write_all(data.trim().as_bytes())
-            .expect(format!(
-                "failed to write data to kernel interface ({})",
+        .open(&filepath);
+
+    let mut file = match file {
+        Ok(file) => file,
+        // Unprivileged write: fall back to logind instead of
+        // demanding root or udev write rules.
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            let value = data.trim().parse::<u32>()?;
+
+            logind_set_brightness(filepath, value)?;
+
+            println!(
+                "Wrote data ({:?}) to kernel interface ({}) via logind",
+                &data.as_bytes(),
                 &filepath
-            ).as_str());
+            );
+
+            return Ok(());
+        },
+        Err(err) => return Err(BlctlError::Io(err))
+    };
+
+    file.write_all(data.trim().as_bytes())?;
 
     println!(
         "Wrote data ({:?}) to kernel interface ({})",
         &data.as_bytes(),
         &filepath
     );
+
+    Ok(())
+}
+
+/// Watches the sysfs brightness file with `inotify` and emits a
+/// [BacklightController::brightness_changed] signal on every change.
+///
+/// Runs for the lifetime of the service as a background task. Each
+/// `IN_MODIFY`/`IN_CLOSE_WRITE` event triggers a fresh [ki_read] of
+/// the current and maximum values, which are then broadcast over the
+/// connection. The watch reads the active device from the shared
+/// [DevicePaths] and re-arms itself within a second of a
+/// [BacklightController::select_device] switch, so signals always
+/// track the selected backlight rather than the startup one.
+///
+/// # Limitations
+///
+/// `inotify` only fires when the file is written through the VFS, so
+/// it catches our own [ki_write]s and other userspace writers but not
+/// brightness changes driven inside the kernel — hardware brightness
+/// keys and firmware updates move `actual_brightness`/`brightness`
+/// without a `write(2)`, so no `IN_MODIFY`/`IN_CLOSE_WRITE` arrives.
+/// Catching those needs `poll(2)`/`epoll(7)` on the sysfs attribute
+/// waiting for `POLLPRI`/`POLLERR`, the mechanism sysfs exposes for
+/// kernel-internal value changes; this watch does not use it, so it
+/// misses hardware-key adjustments. The one-second re-arm timeout bounds how
+/// stale the selection can get but does not poll the value itself.
+///
+/// A read, parse, or signal failure is logged and the loop keeps
+/// running rather than tearing the whole watch down on a transient
+/// error.
+async fn watch_brightness(
+    conn: Connection,
+    paths: Arc<DevicePaths>
+) -> Result<(), Box<dyn Error>> {
+    let iface_ref = conn
+        .object_server()
+        .interface::<_, BacklightController>("/me/xela/blctl")
+        .await?;
+
+    loop {
+        let watched = paths.brightness();
+
+        let inotify = Inotify::init()?;
+        inotify.watches().add(
+            &watched,
+            WatchMask::MODIFY | WatchMask::CLOSE_WRITE
+        )?;
+
+        let mut stream = inotify.into_event_stream(vec![0u8; 1024])?;
+
+        // Watch this device until `select_device` points elsewhere,
+        // re-checking on a timeout so a switch can't go unnoticed
+        // while the file is idle.
+        loop {
+            match async_std::future::timeout(
+                Duration::from_secs(1),
+                stream.next()
+            ).await {
+                Ok(Some(event)) => {
+                    if let Err(err) = event {
+                        eprintln!("brightness watch event failed: {:?}", err);
+                        continue;
+                    }
+
+                    let value = match ki_read(&paths.brightness())
+                        .and_then(|data| Ok(data.trim().parse::<u32>()?))
+                    {
+                        Ok(value) => value,
+                        Err(err) => {
+                            eprintln!("brightness watch read failed: {:?}", err);
+                            continue;
+                        }
+                    };
+
+                    let max = match ki_read(&paths.max_brightness())
+                        .and_then(|data| Ok(data.trim().parse::<u32>()?))
+                    {
+                        Ok(max) => max,
+                        Err(err) => {
+                            eprintln!("brightness watch max read failed: {:?}", err);
+                            continue;
+                        }
+                    };
+
+                    if let Err(err) = BacklightController::brightness_changed(
+                        iface_ref.signal_context(),
+                        value,
+                        max
+                    ).await {
+                        eprintln!("brightness_changed signal failed: {:?}", err);
+                    }
+                },
+                Ok(None) => return Ok(()),
+                Err(_) => {
+                    if paths.brightness() != watched {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Clamps a brightness level into `[min, max]`.
+///
+/// `min.min(max)` keeps the floor from ever exceeding the ceiling, so
+/// a `set_min` above the device's maximum clamps to `max` rather than
+/// panicking on an inverted range.
+fn clamp_brightness(value: u32, min: u32, max: u32) -> u32 {
+    value.clamp(min.min(max), max)
+}
+
+/// Reduces `current` by `amount`, clamping at the floor rather than
+/// underflowing past zero.
+fn decrease_brightness(current: u32, amount: u32, min: u32, max: u32) -> u32 {
+    clamp_brightness(current.saturating_sub(amount), min, max)
+}
+
+/// Evaluates the `(lux, brightness%)` curve at `lux` with a monotone
+/// cubic spline.
+///
+/// Uses Fritsch–Carlson tangents so the interpolant never overshoots
+/// between control points — brightness stays monotone in lux rather
+/// than wiggling the way a naive cubic spline would. `points` is
+/// assumed sorted by lux (as [BacklightController::set_auto_curve]
+/// leaves it); readings outside the control range clamp to the
+/// nearest endpoint.
+fn interpolate_curve(points: &[(f64, u32)], lux: f64) -> f64 {
+    if points.is_empty() {
+        return 0f64;
+    }
+
+    if lux <= points[0].0 {
+        return points[0].1 as f64;
+    }
+
+    let last = points.len() - 1;
+    if lux >= points[last].0 {
+        return points[last].1 as f64;
+    }
+
+    let xs: Vec<f64> = points.iter().map(|p| p.0).collect();
+    let ys: Vec<f64> = points.iter().map(|p| p.1 as f64).collect();
+
+    // Secant slopes between consecutive points.
+    let mut delta = vec![0f64; last];
+    for i in 0..last {
+        delta[i] = (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i]);
+    }
+
+    // Initial tangents: one-sided at the ends, averaged in between,
+    // and flat at a local extremum so it isn't overshot.
+    let mut m = vec![0f64; points.len()];
+    m[0] = delta[0];
+    m[last] = delta[last - 1];
+    for i in 1..last {
+        m[i] = if delta[i - 1] * delta[i] <= 0f64 {
+            0f64
+        } else {
+            (delta[i - 1] + delta[i]) / 2f64
+        };
+    }
+
+    // Fritsch–Carlson monotonicity adjustment.
+    for i in 0..last {
+        if delta[i] == 0f64 {
+            m[i] = 0f64;
+            m[i + 1] = 0f64;
+            continue;
+        }
+
+        let alpha = m[i] / delta[i];
+        let beta = m[i + 1] / delta[i];
+        let sum = alpha * alpha + beta * beta;
+        if sum > 9f64 {
+            let tau = 3f64 / sum.sqrt();
+            m[i] = tau * alpha * delta[i];
+            m[i + 1] = tau * beta * delta[i];
+        }
+    }
+
+    // Locate the segment containing `lux` and evaluate the Hermite
+    // basis on it.
+    let mut seg = 0;
+    while seg < last && lux > xs[seg + 1] {
+        seg += 1;
+    }
+
+    let h = xs[seg + 1] - xs[seg];
+    let t = (lux - xs[seg]) / h;
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let h00 = 2f64 * t3 - 3f64 * t2 + 1f64;
+    let h10 = t3 - 2f64 * t2 + t;
+    let h01 = -2f64 * t3 + 3f64 * t2;
+    let h11 = t3 - t2;
+
+    h00 * ys[seg] + h10 * h * m[seg] + h01 * ys[seg + 1] + h11 * h * m[seg + 1]
+}
+
+/// Reads the ambient illuminance in lux from the first IIO sensor
+/// under `/sys/bus/iio/devices` that exposes one.
+///
+/// Prefers `in_illuminance_input` (already scaled to lux); otherwise
+/// falls back to `in_illuminance_raw` multiplied by the device's
+/// `in_illuminance_scale` when present. Returns `None` when no sensor
+/// can be read.
+fn read_illuminance() -> Option<f64> {
+    let entries = std::fs::read_dir("/sys/bus/iio/devices").ok()?;
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+
+        let input = path.join("in_illuminance_input");
+        if let Ok(data) = std::fs::read_to_string(&input) {
+            if let Ok(lux) = data.trim().parse::<f64>() {
+                return Some(lux);
+            }
+        }
+
+        let raw = path.join("in_illuminance_raw");
+        if let Ok(data) = std::fs::read_to_string(&raw) {
+            if let Ok(value) = data.trim().parse::<f64>() {
+                let scale = std::fs::read_to_string(path.join("in_illuminance_scale"))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .unwrap_or(1f64);
+                return Some(value * scale);
+            }
+        }
+    }
+
+    None
+}
+
+/// Polls the ambient light sensor and drives the backlight while
+/// auto-brightness is enabled.
+///
+/// Runs for the lifetime of the service. The cadence is adaptive:
+/// ~2s while readings are stable, dropping to ~100ms for a short
+/// window after a large lux change so the screen reacts quickly to
+/// entering or leaving a bright environment. Readings are mapped onto
+/// the backlight through [interpolate_curve], clamped to the
+/// configured minimum floor, and skipped entirely during the
+/// [AUTO_GRACE] window that follows a manual change. The active
+/// device is read from the shared [DevicePaths] each poll so it
+/// tracks [BacklightController::select_device].
+async fn watch_ambient_light(
+    paths: Arc<DevicePaths>,
+    auto: Arc<AutoState>
+) {
+    let mut last_lux: Option<f64> = None;
+    let mut fast_until: Option<Instant> = None;
+
+    loop {
+        let fast = fast_until.map_or(false, |until| Instant::now() < until);
+        let cadence = if fast {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(2)
+        };
+        async_std::task::sleep(cadence).await;
+
+        if !auto.enabled.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        let lux = match read_illuminance() {
+            Some(lux) => lux,
+            None => continue
+        };
+
+        // Enter the fast cadence window after a large change.
+        if let Some(prev) = last_lux {
+            if (lux - prev).abs() > (prev * 0.2f64).max(50f64) {
+                fast_until = Some(Instant::now() + Duration::from_secs(1));
+            }
+        }
+        last_lux = Some(lux);
+
+        // Let manual overrides stick for a grace period.
+        if auto.in_grace() {
+            continue;
+        }
+
+        let curve = auto.curve.lock().unwrap().clone();
+        if curve.is_empty() {
+            continue;
+        }
+
+        let max = match ki_read(&paths.max_brightness())
+            .ok()
+            .and_then(|data| data.trim().parse::<u32>().ok())
+        {
+            Some(max) => max,
+            None => continue
+        };
+
+        let min_percent = auto.min_percent.load(Ordering::SeqCst);
+        let percent = interpolate_curve(&curve, lux)
+            .clamp(min_percent as f64, 100f64);
+
+        let level = (percent / 100f64 * max as f64).round() as u32;
+        if let Err(err) = ki_write(&paths.brightness(), level.to_string()) {
+            eprintln!("auto-brightness write failed: {}", err);
+        }
+    }
 }
 
 #[async_std::main]
@@ -201,22 +991,112 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Creating BacklightController");
 
+    let paths = Arc::new(DevicePaths::new(
+        "/sys/class/backlight/amdgpu_bl0/brightness".to_string(),
+        "/sys/class/backlight/amdgpu_bl0/max_brightness".to_string()
+    ));
+
+    let auto = Arc::new(AutoState::new());
+
     let bl_controller = BacklightController {
-        kernel_brightness_fp: "/sys/class/backlight/amdgpu_bl0/brightness".to_string(),
-        kernel_max_brightness_fp: "/sys/class/backlight/amdgpu_bl0/max_brightness".to_string()
+        paths: paths.clone(),
+        min: 1,
+        fade_generation: Arc::new(AtomicU64::new(0)),
+        auto: auto.clone()
     };
 
     println!("Building connection");
 
-    let _conn = ConnectionBuilder::system()?
+    let conn = ConnectionBuilder::system()?
         .name("me.xela.blctl")?
         .serve_at("/me/xela/blctl", bl_controller)?
         .build()
         .await?;
 
+    println!("Spawning brightness watch task");
+
+    let watch_conn = conn.clone();
+    let watch_paths = paths.clone();
+    async_std::task::spawn(async move {
+        if let Err(err) = watch_brightness(watch_conn, watch_paths).await {
+            eprintln!("brightness watch task exited: {}", err);
+        }
+    });
+
+    println!("Spawning ambient light watch task");
+
+    async_std::task::spawn(watch_ambient_light(paths, auto));
+
     println!("Awaiting message");
 
     pending::<()>().await;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_brightness, decrease_brightness, interpolate_curve};
+
+    #[test]
+    fn decrease_clamps_at_floor_without_underflowing() {
+        // Dropping below the floor lands on the floor, not a wrapped
+        // near-u32::MAX value.
+        assert_eq!(decrease_brightness(30, 100, 10, 255), 10);
+        // Exactly reaching zero with no floor stays at zero.
+        assert_eq!(decrease_brightness(5, 5, 0, 255), 0);
+        // A normal step lands on the arithmetic result.
+        assert_eq!(decrease_brightness(200, 50, 0, 255), 150);
+    }
+
+    #[test]
+    fn clamp_survives_min_above_max() {
+        // A `set_min` larger than the device maximum must not panic on
+        // an inverted clamp range; the value pins to `max`.
+        assert_eq!(clamp_brightness(120, 500, 255), 255);
+        assert_eq!(decrease_brightness(255, 10, 500, 255), 255);
+    }
+
+    #[test]
+    fn empty_curve_is_zero() {
+        assert_eq!(interpolate_curve(&[], 500f64), 0f64);
+    }
+
+    #[test]
+    fn single_point_is_constant() {
+        let points = [(100f64, 42u32)];
+        assert_eq!(interpolate_curve(&points, 0f64), 42f64);
+        assert_eq!(interpolate_curve(&points, 100f64), 42f64);
+        assert_eq!(interpolate_curve(&points, 10_000f64), 42f64);
+    }
+
+    #[test]
+    fn clamps_to_endpoints() {
+        let points = [(10f64, 5u32), (100f64, 80u32)];
+        // Below the first and above the last control point the curve
+        // flattens to the nearest endpoint.
+        assert_eq!(interpolate_curve(&points, 0f64), 5f64);
+        assert_eq!(interpolate_curve(&points, 1_000f64), 80f64);
+    }
+
+    #[test]
+    fn passes_through_control_points() {
+        let points = [(0f64, 0u32), (50f64, 30u32), (200f64, 100u32)];
+        for &(lux, brightness) in &points {
+            assert!((interpolate_curve(&points, lux) - brightness as f64).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn is_monotonic() {
+        let points = [(0f64, 0u32), (100f64, 50u32), (400f64, 100u32)];
+        let mut previous = f64::NEG_INFINITY;
+        let mut lux = 0f64;
+        while lux <= 400f64 {
+            let value = interpolate_curve(&points, lux);
+            assert!(value + 1e-9 >= previous, "dropped at lux {}", lux);
+            previous = value;
+            lux += 1f64;
+        }
+    }
+}